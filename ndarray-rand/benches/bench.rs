@@ -1,11 +1,10 @@
 #![feature(test)]
 
-extern crate rand;
 extern crate ndarray;
 extern crate ndarray_rand;
 extern crate test;
 
-use rand::distributions::Normal;
+use ndarray_rand::rand::distributions::Normal;
 use ndarray::Array;
 use ndarray_rand::RandomExt;
 use ndarray_rand::F32;
@@ -9,14 +9,19 @@
 //! Constructors for randomized arrays. `rand` integration for `ndarray`.
 //!
 //! See [**`RandomExt`**](trait.RandomExt.html) for usage examples.
-extern crate rand;
+pub extern crate rand;
 extern crate ndarray;
+extern crate num_traits;
 
-use rand::Rng;
-use rand::distributions::Sample;
-use rand::distributions::IndependentSample;
+use std::marker::PhantomData;
 
-use ndarray::{ArrayBase, Dimension, DataOwned};
+use rand::{Rng, SeedableRng};
+use rand::distributions::Distribution;
+use rand::distributions::Uniform;
+use rand::rngs::StdRng;
+use num_traits::AsPrimitive;
+
+use ndarray::{Array, Array1, ArrayBase, Axis, Data, DataMut, DataOwned, Dimension, RemoveAxis};
 use ndarray::ShapeBuilder;
 
 /// Constructors for n-dimensional arrays with random elements.
@@ -24,7 +29,14 @@ use ndarray::ShapeBuilder;
 /// This trait extends ndarray’s `ArrayBase` and can not be implemented
 /// for other types.
 ///
-/// The default Rng is a fast automatically seeded rng (currently `rand::weak_rng`).
+/// The default Rng is a fast automatically seeded rng (currently `rand::thread_rng`).
+///
+/// Distributions must implement the [`rand::distributions::Distribution`] trait
+/// from *this crate's* re-exported `rand` (`ndarray_rand::rand`), not just any
+/// `rand` that happens to be on a caller's dependency tree — otherwise a
+/// version mismatch between the two `rand`s shows up as a confusing "trait
+/// not implemented" error. Construct distributions via `ndarray_rand::rand::distributions`
+/// to always pick up a compatible version.
 pub trait RandomExt<S, D>
 where
     S: DataOwned,
@@ -36,16 +48,15 @@ where
     /// ***Panics*** if the number of elements overflows usize.
     ///
     /// ```
-    /// extern crate rand;
     /// extern crate ndarray;
     /// extern crate ndarray_rand;
     ///
-    /// use rand::distributions::Range;
+    /// use ndarray_rand::rand::distributions::Uniform;
     /// use ndarray::Array;
     /// use ndarray_rand::RandomExt;
     ///
     /// # fn main() {
-    /// let a = Array::random((2, 5), Range::new(0., 10.));
+    /// let a = Array::random((2, 5), Uniform::new(0., 10.));
     /// println!("{:8.4}", a);
     /// // Example Output:
     /// // [[  8.6900,   6.9824,   3.8922,   6.5861,   2.4890],
@@ -53,7 +64,7 @@ where
     /// # }
     fn random<Sh, IdS>(shape: Sh, distribution: IdS) -> ArrayBase<S, D>
     where
-        IdS: IndependentSample<S::Elem>,
+        IdS: Distribution<S::Elem>,
         Sh: ShapeBuilder<Dim = D>;
 
     /// Create an array with shape `dim` with elements drawn from
@@ -62,8 +73,20 @@ where
     /// ***Panics*** if the number of elements overflows usize.
     fn random_using<Sh, IdS, R>(shape: Sh, distribution: IdS, rng: &mut R) -> ArrayBase<S, D>
     where
-        IdS: IndependentSample<S::Elem>,
-        R: Rng,
+        IdS: Distribution<S::Elem>,
+        R: Rng + ?Sized,
+        Sh: ShapeBuilder<Dim = D>;
+
+    /// Create an array with shape `dim` with elements drawn from
+    /// `distribution`, using a deterministic rng seeded from `seed`.
+    ///
+    /// This is a shorthand for `random_using` with a freshly seeded
+    /// [`StdRng`](rand::rngs::StdRng), useful for reproducible test fixtures
+    /// and benchmarks without having to thread an `&mut R` through several
+    /// layers of array construction.
+    fn random_with_seed<Sh, IdS>(shape: Sh, distribution: IdS, seed: u64) -> ArrayBase<S, D>
+    where
+        IdS: Distribution<S::Elem>,
         Sh: ShapeBuilder<Dim = D>;
 }
 
@@ -74,63 +97,306 @@ where
 {
     fn random<Sh, IdS>(shape: Sh, dist: IdS) -> ArrayBase<S, D>
     where
-        IdS: IndependentSample<S::Elem>,
+        IdS: Distribution<S::Elem>,
         Sh: ShapeBuilder<Dim = D>,
     {
-        Self::random_using(shape, dist, &mut rand::weak_rng())
+        Self::random_using(shape, dist, &mut rand::thread_rng())
     }
 
     fn random_using<Sh, IdS, R>(shape: Sh, dist: IdS, rng: &mut R) -> ArrayBase<S, D>
     where
-        IdS: IndependentSample<S::Elem>,
-        R: Rng,
+        IdS: Distribution<S::Elem>,
+        R: Rng + ?Sized,
         Sh: ShapeBuilder<Dim = D>,
     {
-        Self::from_shape_fn(shape, |_| dist.ind_sample(rng))
+        Self::from_shape_fn(shape, |_| dist.sample(rng))
+    }
+
+    fn random_with_seed<Sh, IdS>(shape: Sh, dist: IdS, seed: u64) -> ArrayBase<S, D>
+    where
+        IdS: Distribution<S::Elem>,
+        Sh: ShapeBuilder<Dim = D>,
+    {
+        Self::random_using(shape, dist, &mut StdRng::seed_from_u64(seed))
+    }
+}
+
+/// Descriptor used by [`ArrayBase::sample_axis`] and
+/// [`ArrayBase::sample_axis_using`] to pick how lane indices are drawn
+/// along an axis.
+#[derive(Copy, Clone, Debug)]
+pub enum SamplingStrategy {
+    /// Sample indices without replacement, i.e. each lane is picked at most once.
+    WithoutReplacement,
+    /// Sample indices with replacement, i.e. a lane may be picked more than once.
+    WithReplacement,
+}
+
+/// Random sampling of lanes along an axis.
+///
+/// This trait extends ndarray’s `ArrayBase` and can not be implemented
+/// for other types. Unlike [`RandomExt`], it only requires read access to
+/// the array (`S: Data`), so it is available for array views as well as
+/// owned arrays.
+pub trait SampleExt<S, D>
+where
+    S: Data,
+    D: RemoveAxis,
+{
+    /// Randomly sample `n_samples` lanes along `axis`, using the default rng.
+    ///
+    /// For `SamplingStrategy::WithoutReplacement`, `n_samples` distinct indices
+    /// in `0..self.len_of(axis)` are drawn.
+    ///
+    /// ***Panics*** if `strategy` is `WithoutReplacement` and `n_samples` is
+    /// greater than `self.len_of(axis)`, or if `n_samples` is nonzero and
+    /// `self.len_of(axis)` is zero.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate ndarray;
+    /// extern crate ndarray_rand;
+    ///
+    /// use ndarray::Axis;
+    /// use ndarray_rand::{SampleExt, SamplingStrategy};
+    ///
+    /// # fn main() {
+    /// let a = array![[1, 2], [3, 4], [5, 6]];
+    /// let b = a.sample_axis(Axis(0), 2, SamplingStrategy::WithoutReplacement);
+    /// # }
+    /// ```
+    fn sample_axis(&self, axis: Axis, n_samples: usize, strategy: SamplingStrategy) -> Array<S::Elem, D>
+    where
+        S::Elem: Copy;
+
+    /// Randomly sample `n_samples` lanes along `axis`, using a specific Rng `rng`.
+    ///
+    /// ***Panics*** if `strategy` is `WithoutReplacement` and `n_samples` is
+    /// greater than `self.len_of(axis)`, or if `n_samples` is nonzero and
+    /// `self.len_of(axis)` is zero.
+    fn sample_axis_using<R>(
+        &self,
+        axis: Axis,
+        n_samples: usize,
+        strategy: SamplingStrategy,
+        rng: &mut R,
+    ) -> Array<S::Elem, D>
+    where
+        S::Elem: Copy,
+        R: Rng + ?Sized;
+}
+
+impl<S, D> SampleExt<S, D> for ArrayBase<S, D>
+where
+    S: Data,
+    D: RemoveAxis,
+{
+    fn sample_axis(&self, axis: Axis, n_samples: usize, strategy: SamplingStrategy) -> Array<S::Elem, D>
+    where
+        S::Elem: Copy,
+    {
+        self.sample_axis_using(axis, n_samples, strategy, &mut rand::thread_rng())
+    }
+
+    fn sample_axis_using<R>(
+        &self,
+        axis: Axis,
+        n_samples: usize,
+        strategy: SamplingStrategy,
+        rng: &mut R,
+    ) -> Array<S::Elem, D>
+    where
+        S::Elem: Copy,
+        R: Rng + ?Sized,
+    {
+        let len = self.len_of(axis);
+        let indices: Vec<usize> = match strategy {
+            SamplingStrategy::WithReplacement => {
+                if n_samples == 0 {
+                    Vec::new()
+                } else if len == 0 {
+                    panic!("SamplingStrategy::WithReplacement: cannot sample from an axis of length 0");
+                } else {
+                    let uniform = Uniform::new(0, len);
+                    (0..n_samples).map(|_| uniform.sample(rng)).collect()
+                }
+            }
+            SamplingStrategy::WithoutReplacement => {
+                if n_samples > len {
+                    panic!(
+                        "SamplingStrategy::WithoutReplacement: cannot take {} samples from \
+                         an axis of length {}",
+                        n_samples, len
+                    );
+                }
+                // Partial Fisher–Yates shuffle: shuffle only as many slots as we need.
+                let mut pool: Vec<usize> = (0..len).collect();
+                let mut indices = Vec::with_capacity(n_samples);
+                for i in 0..n_samples {
+                    let j = Uniform::new(0, len - i).sample(rng);
+                    indices.push(pool[j]);
+                    pool.swap(j, len - i - 1);
+                }
+                indices
+            }
+        };
+        self.select(axis, &indices)
+    }
+}
+
+/// In-place random shuffling of lanes along an axis.
+///
+/// This trait extends ndarray’s `ArrayBase` and can not be implemented
+/// for other types. It requires mutable access to the array (`S: DataMut`),
+/// so it is available for owned arrays and mutable array views alike.
+pub trait ShuffleExt<S, D>
+where
+    S: DataMut,
+    D: RemoveAxis,
+{
+    /// Shuffle the lanes of the array along `axis` in place, using the default rng.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate ndarray;
+    /// extern crate ndarray_rand;
+    ///
+    /// use ndarray::Axis;
+    /// use ndarray_rand::ShuffleExt;
+    ///
+    /// # fn main() {
+    /// let mut a = array![[1, 2], [3, 4], [5, 6]];
+    /// a.shuffle_axis(Axis(0));
+    /// # }
+    /// ```
+    fn shuffle_axis(&mut self, axis: Axis)
+    where
+        S::Elem: Clone;
+
+    /// Shuffle the lanes of the array along `axis` in place, using a specific
+    /// Rng `rng`.
+    ///
+    /// Lanes are permuted with a Fisher–Yates shuffle: for `i` from `len - 1`
+    /// down to `1`, a `j` is drawn uniformly in `0..=i` and the lanes at
+    /// index `i` and `j` are exchanged.
+    fn shuffle_axis_using<R>(&mut self, axis: Axis, rng: &mut R)
+    where
+        S::Elem: Clone,
+        R: Rng + ?Sized;
+}
+
+impl<S, D> ShuffleExt<S, D> for ArrayBase<S, D>
+where
+    S: DataMut,
+    D: RemoveAxis,
+{
+    fn shuffle_axis(&mut self, axis: Axis)
+    where
+        S::Elem: Clone,
+    {
+        self.shuffle_axis_using(axis, &mut rand::thread_rng())
     }
+
+    fn shuffle_axis_using<R>(&mut self, axis: Axis, rng: &mut R)
+    where
+        S::Elem: Clone,
+        R: Rng + ?Sized,
+    {
+        let len = self.len_of(axis);
+        for i in (1..len).rev() {
+            let j = Uniform::new(0, i + 1).sample(rng);
+            if i != j {
+                let lane_i: Vec<S::Elem> = self.index_axis(axis, i).iter().cloned().collect();
+                let lane_j: Vec<S::Elem> = self.index_axis(axis, j).iter().cloned().collect();
+                self.index_axis_mut(axis, i)
+                    .iter_mut()
+                    .zip(lane_j)
+                    .for_each(|(elem, value)| *elem = value);
+                self.index_axis_mut(axis, j)
+                    .iter_mut()
+                    .zip(lane_i)
+                    .for_each(|(elem, value)| *elem = value);
+            }
+        }
+    }
+}
+
+/// Return a random permutation of `0..n` as an owned array, using a specific
+/// Rng `rng`.
+///
+/// The result can be fed into [`ArrayBase::select`](ndarray::ArrayBase::select)
+/// to shuffle an array's lanes without mutating it in place.
+pub fn permutation<R>(n: usize, rng: &mut R) -> Array1<usize>
+where
+    R: Rng + ?Sized,
+{
+    let mut indices: Vec<usize> = (0..n).collect();
+    for i in (1..n).rev() {
+        let j = Uniform::new(0, i + 1).sample(rng);
+        indices.swap(i, j);
+    }
+    Array1::from(indices)
 }
 
-/// A wrapper type that allows casting f64 distributions to f32
+/// A wrapper type that casts the output of a distribution sampling `U` into
+/// any other numeric type `T`.
+///
+/// This generalizes the old `F32` wrapper (kept below as its own dedicated
+/// wrapper for backward compatibility) so a single adapter covers every
+/// float-to-float, float-to-integer or integer-to-integer cast, e.g. drawing
+/// an `i32` array from an `f64` distribution. `U` can't be inferred from
+/// `Distribution<U>` alone, so it has to be a parameter of `AsType` itself
+/// (`AsType<T, U, S>` rather than just `AsType<T, S>`), which means callers
+/// spell it out as `AsType::<i32, f64, _>::new(..)` instead of the shorter
+/// `AsType::<i32, _>::new(..)`.
 ///
 /// ```
-/// extern crate rand;
 /// extern crate ndarray;
 /// extern crate ndarray_rand;
 ///
-/// use rand::distributions::Normal;
+/// use ndarray_rand::rand::distributions::Normal;
 /// use ndarray::Array;
-/// use ndarray_rand::{RandomExt, F32};
+/// use ndarray_rand::{RandomExt, AsType};
 ///
 /// # fn main() {
-/// let a = Array::random((2, 5), F32(Normal::new(0., 1.)));
-/// println!("{:8.4}", a);
-/// // Example Output:
-/// // [[ -0.6910,   1.1730,   1.0902,  -0.4092,  -1.7340],
-/// //  [ -0.6810,   0.1678,  -0.9487,   0.3150,   1.2981]]
+/// let a = Array::<i32, _>::random((2, 5), AsType::<i32, f64, _>::new(Normal::new(0., 1.)));
+/// println!("{:8}", a);
 /// # }
+/// ```
 #[derive(Copy, Clone, Debug)]
-pub struct F32<S>(pub S);
+pub struct AsType<T, U, S>(pub S, PhantomData<(T, U)>);
 
-impl<S> Sample<f32> for F32<S>
+impl<T, U, S> AsType<T, U, S> {
+    /// Wrap `distribution`, casting each sampled value to `T`.
+    pub fn new(distribution: S) -> Self {
+        AsType(distribution, PhantomData)
+    }
+}
+
+impl<T, U, S> Distribution<T> for AsType<T, U, S>
 where
-    S: Sample<f64>,
+    S: Distribution<U>,
+    U: AsPrimitive<T>,
+    T: Copy + 'static,
 {
-    fn sample<R>(&mut self, rng: &mut R) -> f32
-    where
-        R: Rng,
-    {
-        self.0.sample(rng) as f32
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.0.sample(rng).as_()
     }
 }
 
-impl<S> IndependentSample<f32> for F32<S>
+/// A wrapper type that casts f64 distributions to f32, kept for backward
+/// compatibility with the original `F32(distribution)` tuple-struct
+/// constructor. It duplicates a special case of [`AsType`] rather than being
+/// a type alias for it, since `AsType` takes an extra type parameter and its
+/// own constructor, neither of which the tuple-struct form supports.
+#[derive(Copy, Clone, Debug)]
+pub struct F32<S>(pub S);
+
+impl<S> Distribution<f32> for F32<S>
 where
-    S: IndependentSample<f64>,
+    S: Distribution<f64>,
 {
-    fn ind_sample<R>(&self, rng: &mut R) -> f32
-    where
-        R: Rng,
-    {
-        self.0.ind_sample(rng) as f32
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        self.0.sample(rng) as f32
     }
 }